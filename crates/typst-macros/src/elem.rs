@@ -0,0 +1,309 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Ident, ItemStruct, Meta, Token};
+
+/// A field parsed out of a struct annotated with `#[elem]`.
+pub struct Field {
+    pub ident: Ident,
+    pub name: String,
+    pub docs: String,
+    pub positional: bool,
+    pub variadic: bool,
+    pub required: bool,
+    pub settable: bool,
+    pub input: TokenStream,
+    pub default: TokenStream,
+    /// Set via `#[deprecated = "use `fill` instead of `color`"]` on the
+    /// field; forwarded into the field's `ParamInfo`.
+    pub deprecation: Option<String>,
+}
+
+impl Field {
+    /// Produce the `ParamInfo` literal for this field, included in the
+    /// element's `params` vector.
+    fn param_info(&self) -> TokenStream {
+        let Field { name, docs, positional, variadic, required, settable, input, default, .. } =
+            self;
+        let named = !positional;
+        let deprecation = match &self.deprecation {
+            Some(message) => quote! { Some(#message) },
+            None => quote! { None },
+        };
+        quote! {
+            ::typst::foundations::ParamInfo {
+                name: #name,
+                docs: #docs,
+                input: #input,
+                default: #default,
+                positional: #positional,
+                named: #named,
+                variadic: #variadic,
+                required: #required,
+                settable: #settable,
+                deprecation: #deprecation,
+            }
+        }
+    }
+}
+
+/// An element parsed out of a struct annotated with `#[elem]`.
+pub struct Elem {
+    pub ident: Ident,
+    pub name: String,
+    pub title: String,
+    pub docs: String,
+    pub fields: Vec<Field>,
+    /// Set via `#[deprecated = "use `heading` with `level: 1` instead"]` on
+    /// the element itself.
+    pub deprecation: Option<String>,
+    /// Set via `#[elem(unstable = "html")]`; `None` means the element is
+    /// stable.
+    pub unstable: Option<String>,
+    /// The names of the capability traits named in `#[elem(Show, ...)]`,
+    /// forwarded verbatim into `NativeElementData::capabilities`.
+    pub capabilities: Vec<String>,
+}
+
+/// The capability traits the `#[elem(...)]` attribute recognizes by name.
+/// Kept in sync with [`NativeElementData::capabilities`](
+/// ::typst::foundations::NativeElementData::capabilities) and the list of
+/// traits `Packed` can hand out a vtable for.
+const CAPABILITIES: &[&str] = &[
+    "Show", "ShowSet", "Synthesize", "Finalize", "Behave", "LocalName",
+];
+
+/// The result of parsing the `#[elem(...)]` argument list: the capability
+/// traits the element implements, and its `unstable = "..."` feature name.
+struct Attrs {
+    capabilities: Vec<String>,
+    unstable: Option<String>,
+}
+
+/// Parse the `#[elem(...)]` argument list into the capability traits it
+/// names and its `unstable = "..."` feature, if any.
+fn parse_attrs(stream: TokenStream) -> syn::Result<Attrs> {
+    let metas = syn::parse2::<Punctuated<Meta, Token![,]>>(stream)?;
+    let mut capabilities = vec![];
+    let mut unstable = None;
+    for meta in metas {
+        match meta {
+            Meta::Path(path) => {
+                let Some(name) = CAPABILITIES.iter().find(|&&c| path.is_ident(c)) else {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "unrecognized capability, must be one of: \
+                         Show, ShowSet, Synthesize, Finalize, Behave, LocalName",
+                    ));
+                };
+                capabilities.push((*name).into());
+            }
+            Meta::NameValue(meta) if meta.path.is_ident("unstable") => {
+                let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) =
+                    &meta.value
+                else {
+                    return Err(syn::Error::new_spanned(
+                        meta.value,
+                        "expected string literal, e.g. `unstable = \"html\"`",
+                    ));
+                };
+                unstable = Some(lit.value());
+            }
+            other => {
+                return Err(syn::Error::new_spanned(other, "unrecognized `#[elem]` argument"));
+            }
+        }
+    }
+    Ok(Attrs { capabilities, unstable })
+}
+
+/// Find a `#[deprecated = "..."]` attribute and extract its message, if any.
+fn deprecation_message(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find(|attr| attr.path().is_ident("deprecated")).and_then(|attr| {
+        let syn::Meta::NameValue(meta) = &attr.meta else { return None };
+        let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = &meta.value else {
+            return None;
+        };
+        Some(lit.value())
+    })
+}
+
+/// Whether the field carries a `#[positional]` attribute, making it
+/// available positionally (and not by name) in the constructor and set
+/// rule.
+fn is_positional(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("positional"))
+}
+
+/// Strip the real `#[deprecated(..)]` attribute, and the macro-only
+/// `#[positional]` marker, from a struct and its fields before re-emitting
+/// them, so the generated item doesn't also trigger rustc's own
+/// deprecation lint or fail to compile on an attribute rustc doesn't know.
+fn strip_deprecated(item: &mut ItemStruct) {
+    item.attrs.retain(|attr| !attr.path().is_ident("deprecated"));
+    for field in &mut item.fields {
+        field
+            .attrs
+            .retain(|attr| !attr.path().is_ident("deprecated") && !attr.path().is_ident("positional"));
+    }
+}
+
+/// Collect the lines of a doc comment into a single Markdown string.
+fn docs_from_attrs(attrs: &[Attribute]) -> String {
+    let mut docs = String::new();
+    for attr in attrs {
+        if !attr.path().is_ident("doc") {
+            continue;
+        }
+        let syn::Meta::NameValue(meta) = &attr.meta else { continue };
+        let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = &meta.value else {
+            continue;
+        };
+        docs.push_str(lit.value().trim());
+        docs.push('\n');
+    }
+    docs.trim().into()
+}
+
+fn parse(stream: TokenStream, item: &ItemStruct) -> syn::Result<Elem> {
+    let ident = item.ident.clone();
+    let name = ident.to_string().to_lowercase();
+    let title = ident.to_string();
+    let docs = docs_from_attrs(&item.attrs);
+    let deprecation = deprecation_message(&item.attrs);
+    let Attrs { capabilities, unstable } = parse_attrs(stream)?;
+    let fields = item
+        .fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().expect("elem fields must be named");
+            Field {
+                name: ident.to_string(),
+                docs: docs_from_attrs(&field.attrs),
+                positional: is_positional(&field.attrs),
+                variadic: false,
+                required: false,
+                settable: true,
+                input: quote! { ::typst::foundations::CastInfo::Any },
+                default: quote! { None },
+                deprecation: deprecation_message(&field.attrs),
+                ident,
+            }
+        })
+        .collect();
+
+    Ok(Elem { ident, name, title, docs, fields, deprecation, unstable, capabilities })
+}
+
+/// Build the `NativeElementData` literal for an `#[elem]`-annotated struct.
+fn create_native_elem_data(elem: &Elem) -> TokenStream {
+    let Elem { ident, name, title, docs, .. } = elem;
+    let params = elem.fields.iter().map(Field::param_info);
+    let deprecation = match &elem.deprecation {
+        Some(message) => quote! { Some(#message) },
+        None => quote! { None },
+    };
+    let stability = match &elem.unstable {
+        Some(feature) => {
+            quote! { ::typst::foundations::Stability::Unstable { feature: #feature } }
+        }
+        None => quote! { ::typst::foundations::Stability::Stable },
+    };
+    let capabilities = &elem.capabilities;
+
+    quote! {
+        ::typst::foundations::NativeElementData {
+            name: #name,
+            title: #title,
+            docs: #docs,
+            keywords: &[],
+            deprecation: #deprecation,
+            stability: #stability,
+            capabilities: &[#(#capabilities),*],
+            construct: <#ident as ::typst::foundations::Construct>::construct,
+            set: <#ident as ::typst::foundations::Set>::set,
+            vtable: <#ident as ::typst::foundations::Capable>::vtable,
+            field_id: #ident::field_id,
+            field_name: #ident::field_name,
+            local_name: None,
+            scope: ::once_cell::sync::Lazy::new(::typst::foundations::Scope::new),
+            params: ::once_cell::sync::Lazy::new(|| vec![#(#params),*]),
+        }
+    }
+}
+
+/// Expand the `#[elem]` attribute macro.
+pub fn elem(stream: TokenStream, item: TokenStream) -> TokenStream {
+    let mut item: ItemStruct = match syn::parse2(item) {
+        Ok(item) => item,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let elem = match parse(stream, &item) {
+        Ok(elem) => elem,
+        Err(err) => return err.to_compile_error(),
+    };
+    let ident = elem.ident.clone();
+    let data = create_native_elem_data(&elem);
+    strip_deprecated(&mut item);
+
+    quote! {
+        #item
+
+        impl ::typst::foundations::NativeElement for #ident {
+            fn data() -> &'static ::typst::foundations::NativeElementData {
+                static DATA: ::typst::foundations::NativeElementData = #data;
+                &DATA
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    fn parse_attrs_collects_known_capabilities() {
+        let attrs = parse_attrs(quote! { Show, Finalize }).unwrap();
+        assert_eq!(attrs.capabilities, vec!["Show".to_string(), "Finalize".to_string()]);
+        assert_eq!(attrs.unstable, None);
+    }
+
+    #[test]
+    fn parse_attrs_reads_the_unstable_feature() {
+        let attrs = parse_attrs(quote! { unstable = "html" }).unwrap();
+        assert_eq!(attrs.unstable, Some("html".to_string()));
+    }
+
+    #[test]
+    fn parse_attrs_errors_on_unrecognized_capability() {
+        // `Sho` is a typo for `Show`; it must be rejected, not silently
+        // dropped, or the generated `capabilities` list would desync from
+        // the traits the type actually implements.
+        let err = parse_attrs(quote! { Sho }).unwrap_err();
+        assert!(err.to_string().contains("unrecognized capability"));
+    }
+
+    #[test]
+    fn parse_attrs_errors_on_non_string_unstable_value() {
+        let err = parse_attrs(quote! { unstable = 1 }).unwrap_err();
+        assert!(err.to_string().contains("expected string literal"));
+    }
+
+    #[test]
+    fn parse_reads_the_positional_attribute_off_fields() {
+        let item: ItemStruct = syn::parse_quote! {
+            struct Test {
+                #[positional]
+                body: Content,
+                fill: Color,
+            }
+        };
+        let elem = parse(TokenStream::new(), &item).unwrap();
+        assert!(elem.fields[0].positional);
+        assert!(!elem.fields[1].positional);
+    }
+}
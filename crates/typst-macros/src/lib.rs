@@ -0,0 +1,11 @@
+//! Procedural macros for Typst.
+
+mod elem;
+
+use proc_macro::TokenStream;
+
+/// Defines a native element.
+#[proc_macro_attribute]
+pub fn elem(stream: TokenStream, item: TokenStream) -> TokenStream {
+    elem::elem(stream.into(), item.into()).into()
+}
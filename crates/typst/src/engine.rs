@@ -0,0 +1,29 @@
+use std::collections::HashSet;
+
+use crate::diag::Sink;
+
+/// Holds all the data needed during compilation: the diagnostics sink and
+/// the set of experimental features the document has opted into.
+pub struct Engine<'a> {
+    /// Accumulates warnings and (non-fatal) errors produced while compiling.
+    pub sink: &'a mut Sink,
+    /// The set of unstable features enabled for this compilation.
+    pub features: Features,
+}
+
+/// The set of unstable features enabled for a compilation, e.g. via the
+/// embedding application's compile options.
+#[derive(Debug, Clone, Default)]
+pub struct Features(HashSet<&'static str>);
+
+impl Features {
+    /// Create a feature set from the given enabled feature names.
+    pub fn from_enabled(features: impl IntoIterator<Item = &'static str>) -> Self {
+        Self(features.into_iter().collect())
+    }
+
+    /// Whether the given feature is enabled.
+    pub fn is_enabled(&self, feature: &str) -> bool {
+        self.0.contains(feature)
+    }
+}
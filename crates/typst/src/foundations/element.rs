@@ -4,21 +4,25 @@ use std::cmp::Ordering;
 use std::fmt::{self, Debug};
 use std::hash::Hash;
 
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
 use once_cell::sync::Lazy;
 use smallvec::SmallVec;
 
-use crate::diag::SourceResult;
+use crate::diag::{bail, warning, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, Args, Content, Dict, Func, ParamInfo, Repr, Scope, Selector, StyleChain,
     Styles, Value,
 };
+#[cfg(test)]
+use crate::foundations::CastInfo;
+use crate::syntax::Span;
 use crate::text::{Lang, Region};
 use crate::util::Static;
 
 #[doc(inline)]
 pub use typst_macros::elem;
+use typst_macros::{func, scope};
 
 /// A document element.
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
@@ -67,22 +71,101 @@ impl Element {
         self.0.keywords
     }
 
+    /// The message to show when the element itself is deprecated, if any.
+    pub fn deprecation(&self) -> Option<&'static str> {
+        self.0.deprecation
+    }
+
+    /// The element's stability, determining whether it is available without
+    /// opting into an unstable feature.
+    pub fn stability(&self) -> &'static Stability {
+        &self.0.stability
+    }
+
     /// Construct an instance of this element.
     pub fn construct(
         self,
         engine: &mut Engine,
         args: &mut Args,
     ) -> SourceResult<Content> {
+        self.check_stability(engine, args.span)?;
+        self.warn_if_deprecated(engine, args);
         (self.0.construct)(engine, args)
     }
 
     /// Execute the set rule for the element and return the resulting style map.
     pub fn set(self, engine: &mut Engine, mut args: Args) -> SourceResult<Styles> {
+        self.check_stability(engine, args.span)?;
+        self.warn_if_deprecated(engine, &args);
         let styles = (self.0.set)(engine, &mut args)?;
         args.finish()?;
         Ok(styles)
     }
 
+    /// Bail if the element is unstable and the document hasn't opted into the
+    /// feature that gates it.
+    fn check_stability(self, engine: &Engine, span: Span) -> SourceResult<()> {
+        let stability = self.stability();
+        if !stability.is_allowed(|feature| engine.features.is_enabled(feature)) {
+            let Stability::Unstable { feature } = stability else { unreachable!() };
+            bail!(
+                span,
+                "{}", Self::stability_error_message(self.name());
+                hint: "{}", Self::stability_error_hint(feature),
+            );
+        }
+        Ok(())
+    }
+
+    /// The error message shown when an unstable element is used without its
+    /// gating feature enabled.
+    fn stability_error_message(name: &str) -> EcoString {
+        eco_format!("`{}` is not enabled in this context", name)
+    }
+
+    /// The hint attached to [`Self::stability_error_message`].
+    fn stability_error_hint(feature: &str) -> EcoString {
+        eco_format!("enable the `{}` feature to use this experimental element", feature)
+    }
+
+    /// Emit warnings for use of the element itself or any of its fields that
+    /// have been deprecated, without affecting construction.
+    fn warn_if_deprecated(self, engine: &mut Engine, args: &Args) {
+        if let Some(message) = self.deprecation() {
+            engine.sink.warn(warning!(args.span, "{}", message));
+        }
+        // Named arguments resolve directly by name; positional arguments
+        // aren't named at the call site, so resolve them against the
+        // element's positional fields in declaration order, the same order
+        // construction consumes them in.
+        let mut positional = self.params().iter().filter(|param| param.positional);
+        for arg in &args.items {
+            let name = Self::resolve_arg_field(&mut positional, arg.name.as_deref());
+            let Some(name) = name else { continue };
+            if let Some(message) = self.deprecated_field_message(name) {
+                engine.sink.warn(warning!(arg.span, "{}", message));
+            }
+        }
+    }
+
+    /// The field an argument corresponds to: its own name if it was passed
+    /// by name, or the next not-yet-consumed positional field otherwise.
+    fn resolve_arg_field<'a>(
+        positional: &mut impl Iterator<Item = &'a ParamInfo>,
+        name: Option<&'a str>,
+    ) -> Option<&'a str> {
+        match name {
+            Some(name) => Some(name),
+            None => positional.next().map(|param| param.name),
+        }
+    }
+
+    /// The deprecation message for the field with the given name, if it has
+    /// one.
+    fn deprecated_field_message(self, name: &str) -> Option<&'static str> {
+        self.params().iter().find(|param| param.name == name)?.deprecation
+    }
+
     /// Whether the element has the given capability.
     pub fn can<C>(self) -> bool
     where
@@ -102,6 +185,27 @@ impl Element {
         self.0.vtable
     }
 
+    /// The names of the capabilities (e.g. `Show`, `Synthesize`, `Finalize`,
+    /// `Behave`) that this element implements.
+    ///
+    /// Unlike [`can`](Self::can), this doesn't require knowing the trait at
+    /// compile time, so it lets code that only has the capability's name
+    /// (e.g. read from a dynamic source) check whether an element supports
+    /// it.
+    pub fn capabilities(&self) -> &'static [&'static str] {
+        self.0.capabilities
+    }
+
+    /// Whether every instance of this element has the capability with the
+    /// given name (e.g. `"Show"`, `"Synthesize"`, `"Finalize"`, `"Behave"`).
+    ///
+    /// This only tells you whether the *type* implements the trait. To ask
+    /// about a specific instance's realization behavior (e.g. whether it is
+    /// `Ignorant` or `Invisible`), use [`Content::behaviour`] instead.
+    pub fn can_by_name(&self, capability: &str) -> bool {
+        self.capabilities().contains(&capability)
+    }
+
     /// Create a selector for this element.
     pub fn select(self) -> Selector {
         Selector::Elem(self, None)
@@ -250,6 +354,9 @@ pub struct NativeElementData {
     pub title: &'static str,
     pub docs: &'static str,
     pub keywords: &'static [&'static str],
+    pub deprecation: Option<&'static str>,
+    pub stability: Stability,
+    pub capabilities: &'static [&'static str],
     pub construct: fn(&mut Engine, &mut Args) -> SourceResult<Content>,
     pub set: fn(&mut Engine, &mut Args) -> SourceResult<Styles>,
     pub vtable: fn(capability: TypeId) -> Option<*const ()>,
@@ -271,6 +378,27 @@ cast! {
     self => Element::from(self).into_value(),
 }
 
+/// Whether an element is ready to be used or gated behind a feature.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Stability {
+    /// The element is stable and can always be used.
+    Stable,
+    /// The element is experimental and can only be used if the document
+    /// opts into the named feature.
+    Unstable { feature: &'static str },
+}
+
+impl Stability {
+    /// Whether this stability level permits use, given a predicate that
+    /// reports whether a named feature is enabled.
+    fn is_allowed(&self, enabled: impl FnOnce(&str) -> bool) -> bool {
+        match self {
+            Self::Stable => true,
+            Self::Unstable { feature } => enabled(feature),
+        }
+    }
+}
+
 /// Synthesize fields on an element. This happens before execution of any show
 /// rule.
 pub trait Synthesize {
@@ -328,6 +456,202 @@ pub enum Behaviour {
     Invisible,
 }
 
+impl Behaviour {
+    /// The name of this behaviour, as exposed to markup through
+    /// [`Content::behaviour_markup`].
+    fn name(self) -> &'static str {
+        match self {
+            Self::Weak(_) => "weak",
+            Self::Supportive => "supportive",
+            Self::Destructive => "destructive",
+            Self::Ignorant => "ignorant",
+            Self::Invisible => "invisible",
+        }
+    }
+}
+
 /// Guards content against being affected by the same show rule multiple times.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Guard(pub usize);
+
+impl Content {
+    /// This instance's interaction behaviour with its neighbors. Elements
+    /// that don't implement [`Behave`] are `Supportive`, the default
+    /// behaviour.
+    pub fn behaviour(&self) -> Behaviour {
+        self.with::<dyn Behave>()
+            .map_or(Behaviour::Supportive, Behave::behaviour)
+    }
+
+    /// Whether this content's element has the capability with the given
+    /// name (e.g. `"Show"`, `"Finalize"`). The instance-level counterpart to
+    /// [`Element::can_by_name`].
+    pub fn can_by_name(&self, capability: &str) -> bool {
+        self.elem().can_by_name(capability)
+    }
+}
+
+#[scope]
+impl Content {
+    /// Whether this content's element has the capability with the given
+    /// name (e.g. `"Show"`, `"Finalize"`, `"Synthesize"`). This lets a show
+    /// rule branch on whether an element participates in a given phase of
+    /// realization (e.g. finalization) instead of hardcoding element names.
+    #[func(name = "can")]
+    pub fn can_markup(&self, capability: EcoString) -> bool {
+        self.can_by_name(&capability)
+    }
+
+    /// The name of this content's interaction behaviour with its neighbors:
+    /// `"weak"`, `"supportive"`, `"destructive"`, `"ignorant"`, or
+    /// `"invisible"`. This lets a show rule adapt to how an element interacts
+    /// with its neighbors (e.g. `"ignorant"`/`"invisible"`) rather than
+    /// hardcoding element names.
+    #[func(name = "behaviour")]
+    pub fn behaviour_markup(&self) -> EcoString {
+        self.behaviour().name().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `NativeElementData` exercising deprecation, stability and
+    /// capabilities, as a real `#[elem]` expansion would produce.
+    fn test_data() -> NativeElementData {
+        NativeElementData {
+            name: "test",
+            title: "Test",
+            docs: "",
+            keywords: &[],
+            deprecation: Some("use `new-test` instead"),
+            stability: Stability::Unstable { feature: "fancy" },
+            capabilities: &["Show", "Finalize"],
+            construct: |_, _| unimplemented!(),
+            set: |_, _| unimplemented!(),
+            vtable: |_| None,
+            field_id: |_| None,
+            field_name: |_| None,
+            local_name: None,
+            scope: Lazy::new(Scope::new),
+            params: Lazy::new(|| {
+                vec![ParamInfo {
+                    name: "color",
+                    docs: "",
+                    input: CastInfo::Any,
+                    default: None,
+                    positional: false,
+                    named: true,
+                    variadic: false,
+                    required: false,
+                    settable: true,
+                    deprecation: Some("use `fill` instead of `color`"),
+                }]
+            }),
+        }
+    }
+
+    fn test_elem() -> Element {
+        Element::from(Box::leak(Box::new(test_data())))
+    }
+
+    #[test]
+    fn deprecation_accessor_reports_the_stored_message() {
+        assert_eq!(test_elem().deprecation(), Some("use `new-test` instead"));
+    }
+
+    #[test]
+    fn deprecated_field_message_matches_only_the_named_field() {
+        let elem = test_elem();
+        assert_eq!(
+            elem.deprecated_field_message("color"),
+            Some("use `fill` instead of `color`")
+        );
+        assert_eq!(elem.deprecated_field_message("fill"), None);
+    }
+
+    #[test]
+    fn stable_is_always_allowed() {
+        assert!(Stability::Stable.is_allowed(|_| false));
+    }
+
+    #[test]
+    fn unstable_is_allowed_only_when_its_feature_is_enabled() {
+        let unstable = Stability::Unstable { feature: "fancy" };
+        assert!(!unstable.is_allowed(|feature| feature != "fancy"));
+        assert!(unstable.is_allowed(|feature| feature == "fancy"));
+    }
+
+    #[test]
+    fn capabilities_are_queryable_by_name() {
+        let elem = test_elem();
+        assert_eq!(elem.capabilities(), &["Show", "Finalize"]);
+        assert!(elem.can_by_name("Show"));
+        assert!(elem.can_by_name("Finalize"));
+        assert!(!elem.can_by_name("Synthesize"));
+    }
+
+    /// A minimal `ParamInfo`, for tests that only care about name,
+    /// positionality and deprecation.
+    fn param(name: &'static str, positional: bool, deprecation: Option<&'static str>) -> ParamInfo {
+        ParamInfo {
+            name,
+            docs: "",
+            input: CastInfo::Any,
+            default: None,
+            positional,
+            named: !positional,
+            variadic: false,
+            required: false,
+            settable: true,
+            deprecation,
+        }
+    }
+
+    #[test]
+    fn resolve_arg_field_passes_through_named_args() {
+        let params = vec![param("first", true, None)];
+        let mut positional = params.iter().filter(|p| p.positional);
+        assert_eq!(
+            Element::resolve_arg_field(&mut positional, Some("color")),
+            Some("color")
+        );
+    }
+
+    #[test]
+    fn resolve_arg_field_matches_positional_args_in_declaration_order() {
+        let params = vec![
+            param("first", true, None),
+            param("second", true, None),
+            param("color", false, Some("use `fill` instead of `color`")),
+        ];
+        let mut positional = params.iter().filter(|p| p.positional);
+        assert_eq!(Element::resolve_arg_field(&mut positional, None), Some("first"));
+        assert_eq!(Element::resolve_arg_field(&mut positional, None), Some("second"));
+        // The named field isn't positional, so it's never handed out here;
+        // a named arg for it resolves via the `Some(name)` branch instead.
+        assert_eq!(Element::resolve_arg_field(&mut positional, None), None);
+    }
+
+    #[test]
+    fn stability_error_message_names_the_element_and_hint_the_feature() {
+        assert_eq!(
+            Element::stability_error_message("html"),
+            "`html` is not enabled in this context"
+        );
+        assert_eq!(
+            Element::stability_error_hint("html"),
+            "enable the `html` feature to use this experimental element"
+        );
+    }
+
+    #[test]
+    fn behaviour_name_covers_every_variant() {
+        assert_eq!(Behaviour::Weak(0).name(), "weak");
+        assert_eq!(Behaviour::Supportive.name(), "supportive");
+        assert_eq!(Behaviour::Destructive.name(), "destructive");
+        assert_eq!(Behaviour::Ignorant.name(), "ignorant");
+        assert_eq!(Behaviour::Invisible.name(), "invisible");
+    }
+}
@@ -0,0 +1,31 @@
+use crate::foundations::{CastInfo, Value};
+
+/// Describes a field on an element, i.e. a named or positional argument
+/// accepted by its constructor and set rule.
+#[derive(Debug, Clone)]
+pub struct ParamInfo {
+    /// The parameter's name.
+    pub name: &'static str,
+    /// Documentation for the parameter.
+    pub docs: &'static str,
+    /// Valid values for the parameter.
+    pub input: CastInfo,
+    /// Creates an instance of the parameter's default value.
+    pub default: Option<fn() -> Value>,
+    /// Is the parameter positional?
+    pub positional: bool,
+    /// Is the parameter named?
+    ///
+    /// Can be true even if `positional` is true if the parameter can be
+    /// given in both variants.
+    pub named: bool,
+    /// Can the parameter be given any number of times?
+    pub variadic: bool,
+    /// Is the parameter required?
+    pub required: bool,
+    /// Can the parameter be used with a set rule?
+    pub settable: bool,
+    /// If set, using this field emits a warning with this migration message
+    /// (e.g. "use `fill` instead of `color`"), without affecting the value.
+    pub deprecation: Option<&'static str>,
+}